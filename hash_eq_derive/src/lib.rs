@@ -0,0 +1,331 @@
+//! Procedural derive macro for [`hash_eq::HashEq`][hash_eq::HashEq].
+//!
+//! `#[derive(HashEq)]` mirrors the way `#[derive(Hash)]` walks a
+//! type's fields in declaration order and writes each one to a
+//! `Hasher`; instead of writing anything, it proves at compile time
+//! that the same walk over a *different*, named type would write
+//! compatible data, and emits `impl HashEq<U> for T {}` once it has.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use hash_eq::HashEq;
+//!
+//! #[derive(Hash)]
+//! struct Name(String);
+//!
+//! #[derive(Hash)]
+//! struct NameRef<'a>(&'a str);
+//!
+//! // Both hash a single `str`, so this manual impl is sound; the
+//! // derive below requires every field pair to implement `HashEq`.
+//! impl<'a> HashEq<Name> for NameRef<'a> {}
+//!
+//! #[derive(Hash, HashEq)]
+//! #[hash_eq(with = "Owned")]
+//! struct Borrowed<'a> {
+//!     name: NameRef<'a>,
+//! }
+//!
+//! #[derive(Hash)]
+//! struct Owned {
+//!     name: Name,
+//! }
+//! ```
+//!
+//! By default each named field of a struct being derived on is paired
+//! with the field of the same name in the type named by
+//! `#[hash_eq(with = "...")]`; a different pairing can be requested
+//! per field with `#[hash_eq(maps_to = "...")]`. Fields of a tuple
+//! struct are paired by position, and `#[hash_eq(at = N)]` pairs a
+//! field with position `N` instead. Fields inside enum variants are
+//! always paired by the same name or position; no per-field attribute
+//! is read there.
+//!
+//! For enums, variants are always paired by declaration-order
+//! position, since that is the order `derive(Hash)` writes as each
+//! variant's discriminant; `#[hash_eq(maps_to = "...")]` on a variant
+//! only renames which identifier is matched in the other type at that
+//! position, it does not reorder the pairing. The macro cannot see
+//! the other type's definition, so it cannot verify that its variants
+//! are actually declared in the same order — that is the caller's
+//! responsibility.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DataEnum, DeriveInput, Fields, Ident, ImplGenerics, LitInt, LitStr,
+    Path, TypeGenerics, WhereClause,
+};
+
+/// `#[derive(HashEq)]`: see the crate-level docs.
+#[proc_macro_derive(HashEq, attributes(hash_eq))]
+pub fn derive_hash_eq(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let with = find_with_path(&input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let assert_fn = match &input.data {
+        Data::Struct(data) => struct_assert_fn(
+            ident, &with, &data.fields, &impl_generics, &ty_generics, &where_clause,
+        )?,
+        Data::Enum(data) => enum_assert_fn(
+            ident, &with, data, &impl_generics, &ty_generics, &where_clause,
+        )?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(HashEq)] does not support unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        #assert_fn
+
+        impl #impl_generics ::hash_eq::HashEq<#with> for #ident #ty_generics #where_clause {}
+    })
+}
+
+/// Reads the `#[hash_eq(with = "path::To::U")]` attribute, which
+/// names the type that this type's `Hash` implementation is being
+/// claimed compatible with.
+fn find_with_path(input: &DeriveInput) -> syn::Result<Path> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("hash_eq") {
+            continue;
+        }
+        let mut found = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") {
+                let lit: LitStr = meta.value()?.parse()?;
+                found = Some(lit.parse::<Path>()?);
+            }
+            Ok(())
+        })?;
+        if let Some(path) = found {
+            return Ok(path);
+        }
+    }
+    Err(syn::Error::new_spanned(
+        &input.ident,
+        "#[derive(HashEq)] requires #[hash_eq(with = \"path::To::U\")] naming the compatible type",
+    ))
+}
+
+/// Reads an optional `#[hash_eq(maps_to = "other_name")]` attribute
+/// off a field or variant.
+fn find_maps_to(attrs: &[syn::Attribute]) -> syn::Result<Option<Ident>> {
+    for attr in attrs {
+        if !attr.path().is_ident("hash_eq") {
+            continue;
+        }
+        let mut found = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("maps_to") {
+                let lit: LitStr = meta.value()?.parse()?;
+                found = Some(format_ident!("{}", lit.value()));
+            }
+            Ok(())
+        })?;
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
+/// Reads an optional `#[hash_eq(at = N)]` attribute off a tuple-struct
+/// field, naming the position of the field it is paired with in the
+/// other type.
+fn find_at(attrs: &[syn::Attribute]) -> syn::Result<Option<usize>> {
+    for attr in attrs {
+        if !attr.path().is_ident("hash_eq") {
+            continue;
+        }
+        let mut found = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("at") {
+                let lit: LitInt = meta.value()?.parse()?;
+                found = Some(lit.base10_parse::<usize>()?);
+            }
+            Ok(())
+        })?;
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
+/// Builds the hidden, never-called function whose body forces rustc
+/// to check `field: HashEq<other_field>` for every paired field,
+/// without the macro ever needing to know the other type's field
+/// types itself.
+fn field_checks(fields: &Fields, t_binding: TokenStream2, u_binding: TokenStream2) -> syn::Result<Vec<TokenStream2>> {
+    let mut checks = Vec::new();
+    match fields {
+        Fields::Named(named) => {
+            for field in &named.named {
+                let name = field.ident.as_ref().unwrap();
+                let target = find_maps_to(&field.attrs)?.unwrap_or_else(|| name.clone());
+                checks.push(quote! {
+                    __hash_eq_assert_field(&#t_binding.#name, &#u_binding.#target);
+                });
+            }
+        }
+        Fields::Unnamed(unnamed) => {
+            for (i, field) in unnamed.unnamed.iter().enumerate() {
+                let idx = syn::Index::from(i);
+                let target = syn::Index::from(
+                    find_at(&field.attrs)?.unwrap_or(i),
+                );
+                checks.push(quote! {
+                    __hash_eq_assert_field(&#t_binding.#idx, &#u_binding.#target);
+                });
+            }
+        }
+        Fields::Unit => {}
+    }
+    Ok(checks)
+}
+
+fn struct_assert_fn(
+    ident: &Ident,
+    with: &Path,
+    fields: &Fields,
+    impl_generics: &ImplGenerics,
+    ty_generics: &TypeGenerics,
+    where_clause: &Option<&WhereClause>,
+) -> syn::Result<TokenStream2> {
+    let fn_name = format_ident!("__hash_eq_assert_{}", ident);
+    let checks = field_checks(fields, quote! { t }, quote! { u })?;
+
+    Ok(quote! {
+        #[allow(dead_code, non_snake_case)]
+        fn #fn_name #impl_generics (t: &#ident #ty_generics, u: &#with) #where_clause {
+            fn __hash_eq_assert_field<A: ::hash_eq::HashEq<B>, B: ::std::hash::Hash>(_a: &A, _b: &B) {}
+            #(#checks)*
+        }
+    })
+}
+
+/// Returns `path` with its final segment's generic arguments (if any)
+/// rewritten to turbofish form, e.g. `Owned<T>` becomes `Owned::<T>`.
+/// Rust requires turbofish for generic args in pattern position, but
+/// `with` is parsed from a plain type-position string, so paths used
+/// to build a variant pattern need this rewrite first.
+fn turbofish(path: &Path) -> Path {
+    let mut path = path.clone();
+    if let Some(segment) = path.segments.last_mut() {
+        if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+            args.colon2_token.get_or_insert_with(Default::default);
+        }
+    }
+    path
+}
+
+/// Builds a pattern matching a variant's fields, binding each field
+/// to a uniquely-named local so both sides of a pair can be
+/// referenced from the match arm body.
+fn variant_pattern(type_path: TokenStream2, name: &Ident, fields: &Fields, prefix: &str) -> TokenStream2 {
+    match fields {
+        Fields::Named(named) => {
+            let bindings = named.named.iter().map(|f| {
+                let field_name = f.ident.as_ref().unwrap();
+                let local = format_ident!("{}_{}", prefix, field_name);
+                quote! { #field_name: #local }
+            });
+            quote! { #type_path::#name { #(#bindings),* } }
+        }
+        Fields::Unnamed(unnamed) => {
+            let bindings = (0..unnamed.unnamed.len()).map(|i| format_ident!("{}_{}", prefix, i));
+            quote! { #type_path::#name(#(#bindings),*) }
+        }
+        Fields::Unit => quote! { #type_path::#name },
+    }
+}
+
+fn variant_field_checks(fields: &Fields, t_prefix: &str, u_prefix: &str) -> Vec<TokenStream2> {
+    match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|f| {
+                let field_name = f.ident.as_ref().unwrap();
+                let t_local = format_ident!("{}_{}", t_prefix, field_name);
+                let u_local = format_ident!("{}_{}", u_prefix, field_name);
+                quote! { __hash_eq_assert_field(#t_local, #u_local); }
+            })
+            .collect(),
+        Fields::Unnamed(unnamed) => (0..unnamed.unnamed.len())
+            .map(|i| {
+                let t_local = format_ident!("{}_{}", t_prefix, i);
+                let u_local = format_ident!("{}_{}", u_prefix, i);
+                quote! { __hash_eq_assert_field(#t_local, #u_local); }
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    }
+}
+
+/// Builds the hidden assert function for an enum.
+///
+/// `derive(Hash)` writes a variant's declaration-order position as
+/// the very first thing it hashes, so `T`'s `n`th variant is only
+/// hash-compatible with `with`'s `n`th variant. The macro pairs
+/// variants by their declaration position regardless of name, and
+/// `#[hash_eq(maps_to = "...")]` only renames the pattern used to
+/// match the `with`-side variant at that position — it cannot reorder
+/// the pairing.
+///
+/// This cannot be checked against `with`'s *actual* declaration order,
+/// since a derive macro only sees the item it is attached to; callers
+/// are responsible for ensuring the two enums' variants are declared
+/// in the same order before relying on this derive.
+fn enum_assert_fn(
+    ident: &Ident,
+    with: &Path,
+    data: &DataEnum,
+    impl_generics: &ImplGenerics,
+    ty_generics: &TypeGenerics,
+    where_clause: &Option<&WhereClause>,
+) -> syn::Result<TokenStream2> {
+    let fn_name = format_ident!("__hash_eq_assert_{}", ident);
+    let with_pat = turbofish(with);
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        let target_name = find_maps_to(&variant.attrs)?.unwrap_or_else(|| variant.ident.clone());
+        let t_pat = variant_pattern(quote! { #ident }, &variant.ident, &variant.fields, "t");
+        let u_pat = variant_pattern(quote! { #with_pat }, &target_name, &variant.fields, "u");
+        let checks = variant_field_checks(&variant.fields, "t", "u");
+
+        arms.push(quote! {
+            (#t_pat, #u_pat) => { #(#checks)* }
+        });
+    }
+
+    Ok(quote! {
+        #[allow(dead_code, non_snake_case, unreachable_patterns, unused_variables)]
+        fn #fn_name #impl_generics (t: &#ident #ty_generics, u: &#with) #where_clause {
+            fn __hash_eq_assert_field<A: ::hash_eq::HashEq<B>, B: ::std::hash::Hash>(_a: &A, _b: &B) {}
+            match (t, u) {
+                #(#arms)*
+                // Any other pairing means `t` and `u` hold different
+                // variants, which is irrelevant here: this function
+                // is never called, it only exists so rustc type-checks
+                // every declared pairing above.
+                _ => {}
+            }
+        }
+    })
+}
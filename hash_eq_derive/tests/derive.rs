@@ -0,0 +1,9 @@
+//! Compile tests for `#[derive(HashEq)]`, run through `trybuild` so
+//! that a regression in the generated code (rather than in this
+//! crate's own logic) is caught as a compile failure.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/*.rs");
+}
@@ -0,0 +1,20 @@
+//! The same generics-threading requirement, but for an enum, which
+//! builds its assert function separately from a struct's.
+
+use hash_eq::HashEq;
+use std::hash::Hash;
+
+#[derive(Hash, HashEq)]
+#[hash_eq(with = "OwnedChoice<T>")]
+enum BorrowedChoice<T: Hash + HashEq<T>> {
+    A(T),
+    B,
+}
+
+#[derive(Hash)]
+enum OwnedChoice<T: Hash> {
+    A(T),
+    B,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+//! A generic type must be derivable: the generated assert function
+//! and `impl` both need to carry the derived item's generics through,
+//! rather than naming the bare, ungenerified type.
+
+use hash_eq::HashEq;
+use std::hash::Hash;
+
+#[derive(Hash, HashEq)]
+#[hash_eq(with = "Owned<T>")]
+struct Borrowed<T: Hash + HashEq<T>>(T);
+
+#[derive(Hash)]
+struct Owned<T: Hash>(T);
+
+fn main() {}
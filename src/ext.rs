@@ -2,7 +2,7 @@ use std::hash::{Hash, Hasher};
 use std::collections::hash_map::{HashMap, RawEntryMut};
 use std::hash::BuildHasher;
 
-use crate::HashEq;
+use crate::{verify_hash_eq, HashEq};
 
 /// An extension trait which allows using any key type `Q` that
 /// implements `HashEq<K>` and `PartialEq<K>` to perform lookups in a
@@ -15,6 +15,22 @@ pub trait HashMapExt<K: Hash, V, S, Q> {
     fn get_key_value_hasheq(&self, key: &Q) -> Option<(&K, &V)>;
 }
 
+/// Checks whether `k` is the key a `HashEq`-driven lookup for `key`
+/// was looking for. Under `debug_assertions`, a `PartialEq` match is
+/// also checked against [`verify_hash_eq`], so that a `HashEq` impl
+/// which lies about hashing the same data as `K` panics here instead
+/// of silently corrupting the map.
+fn matches_hasheq<K: Hash, Q: Hash + HashEq<K> + PartialEq<K>>(key: &Q, k: &K) -> bool {
+    let eq = key == k;
+    if eq {
+        debug_assert!(
+            verify_hash_eq(key, k),
+            "HashEq impl is unsound: value compared equal via PartialEq but hashes differently"
+        );
+    }
+    eq
+}
+
 impl<K, V, S, Q> HashMapExt<K, V, S, Q> for HashMap<K, V, S>
     where
     K: Eq + Hash,
@@ -26,13 +42,13 @@ impl<K, V, S, Q> HashMapExt<K, V, S, Q> for HashMap<K, V, S>
     {
         let mut h = self.hasher().build_hasher();
         key.hash(&mut h);
-        self.raw_entry_mut().from_hash(h.finish(), |k| key == k)
+        self.raw_entry_mut().from_hash(h.finish(), |k| matches_hasheq(key, k))
     }
 
     fn get_key_value_hasheq(&self, key: &Q) -> Option<(&K, &V)> {
         let mut h = self.hasher().build_hasher();
         key.hash(&mut h);
-        self.raw_entry().from_hash(h.finish(), |k| key == k)
+        self.raw_entry().from_hash(h.finish(), |k| matches_hasheq(key, k))
     }
 }
 
@@ -2,7 +2,9 @@
 //! type's [`Hash`] implementation while preserving the information of
 //! which `write_` method was used to write them.
 
-use std::hash::Hasher;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 macro_rules! define_hasher_datum {
     ($($method:ident($inttype:ty) -> $var:ident;)+) => {
@@ -14,6 +16,14 @@ macro_rules! define_hasher_datum {
             StartSlice,
             /// Byte from [`Hasher::write`]
             Byte(u8),
+            /// String from [`Hasher::write_str`]
+            Str(String),
+            /// Length prefix from [`Hasher::write_length_prefix`]
+            LengthPrefix(usize),
+            /// The result of a nested [`Hasher::finish`] call, only
+            /// produced when the [`DatumHasher`] was built with
+            /// [`DatumHasher::with_inner`].
+            Finished(u64),
             $($var($inttype)),+
         }
 
@@ -40,6 +50,17 @@ macro_rules! define_hasher_datum {
                         Byte(_) => {
                             panic!("Byte outside of slice in hasher data");
                         },
+                        Str(s) => {
+                            state.write_str(&s);
+                        },
+                        LengthPrefix(len) => {
+                            state.write_length_prefix(len);
+                        },
+                        Finished(_) => {
+                            // Not a write of its own; it only marks
+                            // that a `finish()` checkpoint was
+                            // recorded, for `EqTestCmp` to compare.
+                        },
                         $($var(v) => {
                             state.$method(v);
                         })+
@@ -48,41 +69,91 @@ macro_rules! define_hasher_datum {
             }
         }
 
-        #[derive(Default)]
         /// A hasher which wraps each value it receives in the
         /// appropriate [`HasherDatum`] variant and passes it to a
-        /// [`ConsumeHasherDatum`]. It will also call `finish` on the
-        /// [`ConsumeHasherDatum`] if [`Hasher::finish`] is called on it.
+        /// [`ConsumeHasherDatum`]. By default, calling
+        /// [`Hasher::finish`] on it calls `finish` on the
+        /// [`ConsumeHasherDatum`] in turn, which panics unless that
+        /// consumer overrides it; build the hasher with
+        /// [`with_inner`][Self::with_inner] instead to have it drive
+        /// a real inner [`Hasher`] alongside the recording, so that
+        /// `finish` returns that hasher's actual result.
         pub struct DatumHasher<C> {
-            consumer: C
+            consumer: RefCell<C>,
+            inner: Option<RefCell<Box<dyn Hasher>>>
         }
-        
+
+        impl<C: ConsumeHasherDatum + Default> Default for DatumHasher<C> {
+            fn default() -> Self {
+                Self::new(C::default())
+            }
+        }
+
         impl<C: ConsumeHasherDatum> DatumHasher<C> {
             pub fn new(consumer: C) -> Self {
                 DatumHasher {
-                    consumer
+                    consumer: RefCell::new(consumer),
+                    inner: None
+                }
+            }
+
+            /// Like [`new`][Self::new], but also drives `hasher` over
+            /// every value written to this `DatumHasher`, so that
+            /// [`finish`][Hasher::finish] returns `hasher`'s real
+            /// result instead of panicking.
+            pub fn with_inner<H: Hasher + 'static>(consumer: C, hasher: H) -> Self {
+                DatumHasher {
+                    consumer: RefCell::new(consumer),
+                    inner: Some(RefCell::new(Box::new(hasher)))
                 }
             }
 
             pub fn into_inner(self) -> C {
-                self.consumer
+                self.consumer.into_inner()
             }
         }
 
         impl<C: ConsumeHasherDatum> Hasher for DatumHasher<C> {
             fn finish(&self) -> u64 {
-                self.consumer.finish()
+                match &self.inner {
+                    Some(inner) => {
+                        let result = inner.borrow().finish();
+                        self.consumer.borrow_mut().consume(HasherDatum::Finished(result));
+                        result
+                    },
+                    None => self.consumer.borrow().finish()
+                }
             }
-            
+
             fn write(&mut self, data: &[u8]) {
-                self.consumer.consume(HasherDatum::StartSlice);
+                self.consumer.get_mut().consume(HasherDatum::StartSlice);
                 for &datum in data {
-                    self.consumer.consume(HasherDatum::Byte(datum));
+                    self.consumer.get_mut().consume(HasherDatum::Byte(datum));
+                }
+                if let Some(inner) = &mut self.inner {
+                    inner.get_mut().write(data);
+                }
+            }
+
+            fn write_str(&mut self, s: &str) {
+                self.consumer.get_mut().consume(HasherDatum::Str(s.to_owned()));
+                if let Some(inner) = &mut self.inner {
+                    inner.get_mut().write_str(s);
+                }
+            }
+
+            fn write_length_prefix(&mut self, len: usize) {
+                self.consumer.get_mut().consume(HasherDatum::LengthPrefix(len));
+                if let Some(inner) = &mut self.inner {
+                    inner.get_mut().write_length_prefix(len);
                 }
             }
-        
+
             $(fn $method(&mut self, val: $inttype) {
-                    self.consumer.consume(HasherDatum::$var(val));
+                    self.consumer.get_mut().consume(HasherDatum::$var(val));
+                    if let Some(inner) = &mut self.inner {
+                        inner.get_mut().$method(val);
+                    }
             })+
         }
     }
@@ -168,3 +239,31 @@ impl ConsumeHasherDatum for EqTestCmp {
         self.is_eq = self.is_eq && self.cmp.next() == Some(datum);
     }
 }
+
+/// Checks whether `t` and `u` would be given the exact same sequence
+/// of [`Hasher`] method calls by their [`Hash`] implementations,
+/// i.e. whether a [`HashEq`][crate::HashEq] impl between their types
+/// would actually be sound.
+///
+/// This is the runtime counterpart to the promise a `HashEq` impl
+/// makes; it is too slow to run on every hash (it records and
+/// replays every write rather than just comparing a final `u64`), but
+/// is suited to asserting the contract holds in tests or, under
+/// `debug_assertions`, at the point a `HashEq`-driven lookup finds a
+/// candidate match.
+///
+/// Both sides are built with [`DatumHasher::with_inner`], backed by a
+/// fresh [`DefaultHasher`], rather than [`DatumHasher::new`]; this
+/// way a `Hash` impl that calls [`Hasher::finish`] partway through
+/// (and mixes the result back into further writes) gets a real `u64`
+/// back instead of panicking, and the two `DefaultHasher`s, having
+/// seen identical input up to that point, are guaranteed to agree.
+pub fn verify_hash_eq<T: Hash, U: Hash>(t: &T, u: &U) -> bool {
+    let mut recorder = DatumHasher::with_inner(EqTestAcc::default(), DefaultHasher::new());
+    t.hash(&mut recorder);
+    let cmp: EqTestCmp = recorder.into_inner().into();
+
+    let mut replayer = DatumHasher::with_inner(cmp, DefaultHasher::new());
+    u.hash(&mut replayer);
+    replayer.into_inner().result()
+}
@@ -1,9 +1,21 @@
 #![feature(hash_raw_entry)]
+#![feature(hasher_prefixfree_extras)]
+#![feature(hash_extract_if)]
 use std::hash::Hash;
 
 mod ext;
+mod hash_cmp;
+mod set_ext;
 
 pub use ext::HashMapExt;
+pub use hash_cmp::verify_hash_eq;
+pub use set_ext::HashSetExt;
+
+/// Derives `impl HashEq<U> for T`, checking at compile time that the
+/// two types' `Hash` implementations walk compatible data. See the
+/// macro's own docs for the attributes it accepts.
+#[cfg(feature = "derive")]
+pub use hash_eq_derive::HashEq;
 
 /// Marks two types as having compatible `Hash` implementations and
 /// allows checking whether two values would have the same hash if
@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+use crate::HashEq;
+
+/// An extension trait which allows using any key type `Q` that
+/// implements `HashEq<T>` and `PartialEq<T>` to perform lookups in a
+/// `HashSet`.
+///
+/// This mirrors [`HashMapExt`][crate::HashMapExt], but `HashSet` does
+/// not expose a `raw_entry` API the way `HashMap` does, so there is no
+/// way to jump straight to the bucket holding a candidate element.
+/// These methods instead scan the set's elements, comparing each one
+/// to `key` with `PartialEq`, without ever hashing `key` at all; as a
+/// result they are `O(n)` in the size of the set, unlike the
+/// amortized `O(1)` lookups on `HashMapExt`.
+pub trait HashSetExt<T: Hash, S, Q> {
+    /// Returns `true` if the set contains an element equal to `key`.
+    fn contains_hasheq(&self, key: &Q) -> bool;
+
+    /// Returns a reference to the set's element equal to `key`, if
+    /// any.
+    fn get_hasheq(&self, key: &Q) -> Option<&T>;
+
+    /// Removes and returns the set's element equal to `key`, if any.
+    fn take_hasheq(&mut self, key: &Q) -> Option<T>;
+}
+
+impl<T, S, Q> HashSetExt<T, S, Q> for HashSet<T, S>
+    where
+    T: Eq + Hash,
+    S: BuildHasher,
+    Q: Hash + HashEq<T> + PartialEq<T>
+{
+    fn contains_hasheq(&self, key: &Q) -> bool {
+        self.get_hasheq(key).is_some()
+    }
+
+    fn get_hasheq(&self, key: &Q) -> Option<&T> {
+        self.iter().find(|item| key == *item)
+    }
+
+    fn take_hasheq(&mut self, key: &Q) -> Option<T> {
+        self.extract_if(|item| key == item).next()
+    }
+}
@@ -0,0 +1,70 @@
+#![feature(hasher_prefixfree_extras)]
+
+use std::hash::{Hash, Hasher};
+
+use hash_eq::verify_hash_eq;
+
+struct WritesStr;
+struct WritesRawBytes;
+
+impl Hash for WritesStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_str("ab");
+    }
+}
+
+impl Hash for WritesRawBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(b"ab");
+        state.write_u8(0xff);
+    }
+}
+
+#[test]
+fn agreeing_write_streams_verify_equal() {
+    struct Same1;
+    struct Same2;
+
+    impl Hash for Same1 {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u32(1);
+            state.write_str("x");
+        }
+    }
+
+    impl Hash for Same2 {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u32(1);
+            state.write_str("x");
+        }
+    }
+
+    assert!(verify_hash_eq(&Same1, &Same2));
+}
+
+#[test]
+fn write_str_is_not_confused_with_raw_write() {
+    // `write_str("ab")` and `write(b"ab"); write_u8(0xff)` (what
+    // `Hasher::write_str` does by default) must not verify as equal: a
+    // prefix-free hasher may override `write_str`, which is why
+    // `HasherDatum::Str` records it as its own datum.
+    assert!(!verify_hash_eq(&WritesStr, &WritesRawBytes));
+}
+
+#[test]
+fn finish_calling_hash_impls_no_longer_panic() {
+    struct ChecksumsThenWrites;
+
+    impl Hash for ChecksumsThenWrites {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u8(1);
+            let partial = state.finish();
+            state.write_u64(partial);
+        }
+    }
+
+    // Before `DatumHasher::with_inner` was wired into
+    // `verify_hash_eq`, this would panic inside `ConsumeHasherDatum`'s
+    // default `finish`.
+    assert!(verify_hash_eq(&ChecksumsThenWrites, &ChecksumsThenWrites));
+}